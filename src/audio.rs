@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+use crate::{
+    bullet::DamageEvent,
+    enemy::{PlayerHurtEvent, TargetDeathEvent, PLAYER_BASE},
+    tower::TowerFiredEvent,
+    GameState,
+};
+#[cfg(feature = "tts")]
+use crate::{enemy::GameOverEvent, wave::WaveClearedEvent};
+
+/// Beyond this distance from the `Camera3d` listener, positional sounds are fully
+/// attenuated rather than playing at full volume.
+const MAX_AUDIBLE_DISTANCE: f32 = 60.0;
+
+pub struct InternalAudioPlugin;
+
+impl Plugin for InternalAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(AudioPlugin).add_systems(
+            Update,
+            (play_tower_fire_audio, play_hit_audio, play_base_audio)
+                .run_if(in_state(GameState::Playing)),
+        );
+
+        #[cfg(feature = "tts")]
+        app.add_systems(
+            Update,
+            announce_state_changes.run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// Linear falloff from the listener so sounds near the camera are loud and distant
+/// ones fade out, instead of every event blaring at the same volume.
+fn attenuate(listener: Vec3, source: Vec3) -> f64 {
+    let distance = listener.distance(source);
+    (1.0 - (distance / MAX_AUDIBLE_DISTANCE).clamp(0.0, 1.0)) as f64
+}
+
+fn play_positional(
+    audio: &Audio,
+    asset_server: &AssetServer,
+    listener: &Query<&GlobalTransform, With<Camera3d>>,
+    source_position: Vec3,
+    clip: &str,
+) {
+    let Ok(listener_transform) = listener.get_single() else {
+        return;
+    };
+
+    let volume = attenuate(listener_transform.translation(), source_position);
+    if volume <= 0.0 {
+        return;
+    }
+
+    audio.play(asset_server.load(clip)).with_volume(volume);
+}
+
+fn play_tower_fire_audio(
+    audio: Res<Audio>,
+    asset_server: Res<AssetServer>,
+    listener: Query<&GlobalTransform, With<Camera3d>>,
+    mut fired_events: EventReader<TowerFiredEvent>,
+) {
+    for event in fired_events.iter() {
+        play_positional(
+            &audio,
+            &asset_server,
+            &listener,
+            event.position,
+            "audio/tower_fire.ogg",
+        );
+    }
+}
+
+fn play_hit_audio(
+    audio: Res<Audio>,
+    asset_server: Res<AssetServer>,
+    listener: Query<&GlobalTransform, With<Camera3d>>,
+    mut damage_events: EventReader<DamageEvent>,
+) {
+    for event in damage_events.iter() {
+        play_positional(
+            &audio,
+            &asset_server,
+            &listener,
+            event.position,
+            "audio/hit.ogg",
+        );
+    }
+}
+
+/// Enemy deaths and player damage both play at the base, since that's where the
+/// action that matters to the player (defense succeeding or failing) is happening.
+fn play_base_audio(
+    audio: Res<Audio>,
+    asset_server: Res<AssetServer>,
+    listener: Query<&GlobalTransform, With<Camera3d>>,
+    mut death_events: EventReader<TargetDeathEvent>,
+    mut hurt_events: EventReader<PlayerHurtEvent>,
+) {
+    if death_events.iter().count() > 0 {
+        play_positional(&audio, &asset_server, &listener, PLAYER_BASE, "audio/enemy_death.ogg");
+    }
+    if hurt_events.iter().count() > 0 {
+        play_positional(&audio, &asset_server, &listener, PLAYER_BASE, "audio/base_damaged.ogg");
+    }
+}
+
+/// Optional accessibility affordance: speak key state changes out loud. Gated behind
+/// the `tts` feature since it pulls in an OS text-to-speech backend that sighted play
+/// doesn't need.
+#[cfg(feature = "tts")]
+fn announce_state_changes(
+    mut tts: Local<Option<tts::Tts>>,
+    mut wave_cleared_events: EventReader<WaveClearedEvent>,
+    mut hurt_events: EventReader<PlayerHurtEvent>,
+    mut game_over_events: EventReader<GameOverEvent>,
+) {
+    let wave_cleared = !wave_cleared_events.is_empty();
+    let base_hurt = !hurt_events.is_empty();
+    let game_over = !game_over_events.is_empty();
+    wave_cleared_events.clear();
+    hurt_events.clear();
+    game_over_events.clear();
+
+    if !(wave_cleared || base_hurt || game_over) {
+        return;
+    }
+
+    if tts.is_none() {
+        *tts = tts::Tts::default().ok();
+    }
+    let Some(tts) = tts.as_mut() else {
+        return;
+    };
+
+    // Game over takes priority over a same-frame "wave cleared"/"base under attack".
+    let message = if game_over {
+        "Game over"
+    } else if base_hurt {
+        "Base under attack"
+    } else {
+        "Wave cleared"
+    };
+
+    let _ = tts.speak(message, false);
+}
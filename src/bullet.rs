@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::{
+    enemy::{Health, Target},
+    GameState,
+};
+
+#[derive(Component)]
+pub struct Bullet {
+    pub direction: Vec3,
+    pub speed: f32,
+    pub damage: i32,
+}
+
+#[derive(Component)]
+pub struct Lifetime {
+    pub timer: Timer,
+}
+
+/// Carries what a bullet hit, for how much, and where, decoupling damage
+/// application from whatever else cares to react to it (currently just audio).
+#[derive(Clone, Event)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: i32,
+    pub position: Vec3,
+}
+
+pub struct BulletPlugin;
+
+impl Plugin for BulletPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>().add_systems(
+            Update,
+            (move_bullets, despawn_expired_bullets, handle_bullet_hits)
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+fn move_bullets(mut bullets: Query<(&Bullet, &mut Transform)>, time: Res<Time>) {
+    for (bullet, mut transform) in &mut bullets {
+        let movement = bullet.direction.normalize_or_zero() * bullet.speed * time.delta_seconds();
+        transform.translation += movement;
+    }
+}
+
+fn despawn_expired_bullets(
+    mut commands: Commands,
+    mut bullets: Query<(Entity, &mut Lifetime)>,
+    time: Res<Time>,
+) {
+    for (entity, mut lifetime) in &mut bullets {
+        lifetime.timer.tick(time.delta());
+        if lifetime.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Reacts to Rapier collision events between `Bullet`s and `Target`s, applying damage
+/// and despawning the bullet. `target_death` (in enemy.rs) stays the single place
+/// that despawns a `Target` once its `Health` drops to zero.
+fn handle_bullet_hits(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    bullets: Query<&Bullet>,
+    mut targets: Query<(&mut Health, &GlobalTransform), With<Target>>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for event in collision_events.iter() {
+        let CollisionEvent::Started(a, b, _) = event else {
+            continue;
+        };
+
+        for (bullet_entity, target_entity) in [(*a, *b), (*b, *a)] {
+            let Ok(bullet) = bullets.get(bullet_entity) else {
+                continue;
+            };
+            let Ok((mut health, target_transform)) = targets.get_mut(target_entity) else {
+                continue;
+            };
+
+            health.value -= bullet.damage;
+            damage_events.send(DamageEvent {
+                target: target_entity,
+                amount: bullet.damage,
+                position: target_transform.translation(),
+            });
+            commands.entity(bullet_entity).despawn_recursive();
+            break;
+        }
+    }
+}
@@ -1,29 +1,58 @@
-use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use oxidized_navigation::{NavMesh, NavMeshAffector, NavMeshSettings};
 
-use crate::{assets::GameAssets, player::Player, GameState};
+use crate::{
+    assets::GameAssets,
+    ground::{queue_pathfinding, AsyncPathfindingTasks, PathfindingComplete},
+    player::Player,
+    GameState,
+};
 
 #[derive(Reflect, Component, Default)]
 #[reflect(Component)]
 pub struct Target {
     pub speed: f32,
-    pub path_index: usize,
 }
 
+/// The string-pulled path a `Target` is currently following, populated asynchronously
+/// from the navmesh. Empty until the first pathfinding task for this entity completes.
+#[derive(Component, Default)]
+pub struct NavPath {
+    pub waypoints: Vec<Vec3>,
+    pub index: usize,
+}
+
+/// Marks a `Target` that already has a pathfinding task in flight, so we don't queue
+/// a second one for it every frame while waiting. Carries the request's sequence
+/// number so a result that arrives after the target's path has since been
+/// invalidated and re-queued (e.g. a tower was built mid-flight) can be told apart
+/// from the one actually being waited on.
+#[derive(Component)]
+struct PathPending(u64);
+
 #[derive(Reflect, Component, Default)]
 #[reflect(Component)]
 pub struct Health {
     pub value: i32,
 }
 
-#[derive(Resource)]
-pub struct TargetPath {
-    waypoints: Vec<Vec2>,
-}
+// Where enemies are headed. Mirrors the `TowerBase` spawn position in tower.rs.
+pub(crate) const PLAYER_BASE: Vec3 = Vec3::new(0.0, 2.0, -2.0);
 
 //Can have any data attached (i.e what kind of target or it's value)
 #[derive(Clone, Event)]
 pub struct TargetDeathEvent;
 
+/// Fires when a `Target` reaches the player base and costs the player a point of
+/// health.
+#[derive(Clone, Event)]
+pub struct PlayerHurtEvent;
+
+/// Raised once, when the player's health hits zero.
+#[derive(Clone, Event)]
+pub struct GameOverEvent;
+
 pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
@@ -31,42 +60,49 @@ impl Plugin for EnemyPlugin {
         app.register_type::<Target>()
             .register_type::<Health>()
             .add_event::<TargetDeathEvent>()
-            //Could be loaded from a config or level file
-            .insert_resource(TargetPath {
-                waypoints: vec![
-                    Vec2::new(6.0, 2.0),
-                    Vec2::new(6.0, 6.0),
-                    Vec2::new(9.0, 9.0),
-                ],
-            })
+            .add_event::<PlayerHurtEvent>()
+            .add_event::<GameOverEvent>()
             .add_systems(
                 Update,
-                (hurt_player.after(move_targets), target_death)
+                (
+                    replan_targets_on_affector_change,
+                    queue_target_paths,
+                    poll_target_paths,
+                    move_targets,
+                    hurt_player,
+                    target_death,
+                )
+                    .chain()
                     .run_if(in_state(GameState::Playing)),
-            )
-            .add_systems(Startup, spawn_enemy)
-            .add_systems(Update, move_targets.run_if(in_state(GameState::Playing)));
+            );
     }
 }
 
-fn spawn_enemy(mut commands: Commands, game_assets: Res<GameAssets>) {
-    info!("spawn_enemy...");
-    for i in 1..=1 {
-        let translation = Vec3::new(-2.0 * i as f32, 0.0, 2.5);
-        let transform = Transform::from_translation(translation);
-        commands
-            .spawn(SceneBundle {
-                scene: game_assets.enemy_scene.clone(),
-                transform,
-                ..Default::default()
-            })
-            .insert(Target {
-                speed: 0.25,
-                ..Default::default()
-            })
-            .insert(Health { value: 3 })
-            .insert(Name::new("Target"));
-    }
+/// Spawns a single enemy `Target`. Called by `WavePlugin` on its per-wave spawn timer
+/// rather than directly from a `Startup` system, so waves control the pacing.
+pub fn spawn_target(
+    commands: &mut Commands,
+    game_assets: &GameAssets,
+    translation: Vec3,
+    speed: f32,
+    health: i32,
+) -> Entity {
+    let transform = Transform::from_translation(translation);
+    commands
+        .spawn(SceneBundle {
+            scene: game_assets.enemy_scene.clone(),
+            transform,
+            ..Default::default()
+        })
+        .insert(Target { speed })
+        .insert(NavPath::default())
+        .insert(Health { value: health })
+        // So bullet `Sensor` colliders generate `CollisionEvent`s against this target.
+        .insert(RigidBody::Fixed)
+        .insert(Collider::ball(0.5))
+        .insert(ActiveEvents::COLLISION_EVENTS)
+        .insert(Name::new("Target"))
+        .id()
 }
 
 fn target_death(
@@ -85,52 +121,139 @@ fn target_death(
 
 fn hurt_player(
     mut commands: Commands,
-    targets: Query<(Entity, &Target)>,
-    path: Res<TargetPath>,
+    targets: Query<(Entity, &NavPath), With<Target>>,
     mut player: Query<&mut Player>,
-    // audio: Res<Audio>,
-    asset_server: Res<AssetServer>,
+    mut hurt_events: EventWriter<PlayerHurtEvent>,
+    mut game_over_events: EventWriter<GameOverEvent>,
 ) {
-    for (entity, target) in &targets {
-        // TODO: use collider?
-        if target.path_index >= path.waypoints.len() {
-            commands.entity(entity).despawn_recursive();
+    for (entity, nav_path) in &targets {
+        if nav_path.waypoints.is_empty() || nav_path.index < nav_path.waypoints.len() {
+            continue;
+        }
 
-            //Enemies reaching the end of their path could write an event to cause the player to take damage or play audio
-            // audio.play(asset_server.load("damage.wav"));
+        commands.entity(entity).despawn_recursive();
+        hurt_events.send(PlayerHurtEvent);
 
-            let mut player = player.single_mut();
-            if player.health > 0 {
-                player.health -= 1;
-            }
+        let mut player = player.single_mut();
+        if player.health > 0 {
+            player.health -= 1;
 
             if player.health == 0 {
-                //TODO this could write an event or change the game state
                 info!("GAME OVER");
+                game_over_events.send(GameOverEvent);
             }
         }
     }
 }
 
-fn move_targets(
-    mut targets: Query<(&mut Target, &mut Transform)>,
-    path: Res<TargetPath>,
-    time: Res<Time>,
+/// Queue a navmesh pathfinding request from every target to the player base, for each
+/// target that doesn't have a path yet (freshly spawned, or just invalidated by
+/// `replan_targets_on_affector_change`). Goes through the shared `AsyncPathfindingTasks`
+/// queue in ground.rs, which is a real background task on native and a time-sliced
+/// synchronous queue on wasm32.
+fn queue_target_paths(
+    mut commands: Commands,
+    targets: Query<(Entity, &Transform), (With<Target>, With<NavPath>, Without<PathPending>)>,
+    waypoints: Query<&NavPath>,
+    nav_mesh_settings: Res<NavMeshSettings>,
+    nav_mesh: Res<NavMesh>,
+    mut pathfinding_tasks: ResMut<AsyncPathfindingTasks>,
+    mut next_request_id: Local<u64>,
+) {
+    for (entity, transform) in &targets {
+        let Ok(nav_path) = waypoints.get(entity) else {
+            continue;
+        };
+        if !nav_path.waypoints.is_empty() {
+            continue;
+        }
+
+        *next_request_id += 1;
+        let request_id = *next_request_id;
+
+        queue_pathfinding(
+            &mut pathfinding_tasks,
+            &nav_mesh,
+            &nav_mesh_settings,
+            transform.translation,
+            PLAYER_BASE,
+            Some(1.0),
+            Some(entity),
+            request_id,
+        );
+        commands.entity(entity).insert(PathPending(request_id));
+    }
+}
+
+/// Hand a completed pathfinding result to the `Target` that requested it, so
+/// `move_targets` never stalls waiting on a path. A result whose `request_id` no
+/// longer matches the target's current `PathPending` is stale (the target was
+/// re-queued since) and is dropped rather than applied. A request that completed
+/// with no path found clears `PathPending` without setting `NavPath::waypoints`, so
+/// `queue_target_paths` simply re-queues it next frame instead of leaving the target
+/// stuck waiting forever.
+fn poll_target_paths(
+    mut commands: Commands,
+    mut completed: EventReader<PathfindingComplete>,
+    mut targets: Query<(&mut NavPath, &PathPending)>,
 ) {
-    for (mut target, mut transform) in &mut targets {
+    for event in completed.iter() {
+        let Some(entity) = event.requester else {
+            continue;
+        };
+        let Ok((mut nav_path, pending)) = targets.get_mut(entity) else {
+            continue;
+        };
+        if pending.0 != event.request_id {
+            continue;
+        }
+
+        if let Some(waypoints) = &event.waypoints {
+            nav_path.waypoints = waypoints.clone();
+            nav_path.index = 0;
+        }
+        commands.entity(entity).remove::<PathPending>();
+    }
+}
+
+/// Any time a `NavMeshAffector` is added or removed (a tower gets built, the debug
+/// `X` cube is toggled, ...) the navmesh shape changes, so every live target needs to
+/// route around the new geometry. Clearing `NavPath::waypoints` causes
+/// `queue_target_paths` to re-queue a task for it next frame.
+fn replan_targets_on_affector_change(
+    mut commands: Commands,
+    mut targets: Query<(Entity, &mut NavPath), With<Target>>,
+    added_affectors: Query<(), Added<NavMeshAffector>>,
+    mut removed_affectors: RemovedComponents<NavMeshAffector>,
+) {
+    if added_affectors.is_empty() && removed_affectors.iter().next().is_none() {
+        return;
+    }
+
+    for (entity, mut nav_path) in &mut targets {
+        nav_path.waypoints.clear();
+        nav_path.index = 0;
+        commands.entity(entity).remove::<PathPending>();
+    }
+}
+
+fn move_targets(mut targets: Query<(&Target, &mut NavPath, &mut Transform)>, time: Res<Time>) {
+    for (target, mut nav_path, mut transform) in &mut targets {
+        let Some(&waypoint) = nav_path.waypoints.get(nav_path.index) else {
+            continue;
+        };
+
         let delta = target.speed * time.delta_seconds();
-        let delta_target = path.waypoints[target.path_index] - transform.translation.xz();
+        let delta_target = waypoint - transform.translation;
 
         // This step will get us closer to the goal
         if delta_target.length() > delta {
             let movement = delta_target.normalize() * delta;
-            transform.translation += movement.extend(0.0).xzy();
-            //Copy for ownership reasons
-            let y = transform.translation.y;
-            transform.look_at(path.waypoints[target.path_index].extend(y).xzy(), Vec3::Y);
+            transform.translation += movement;
+            transform.look_at(waypoint, Vec3::Y);
         } else {
             // At current step
-            target.path_index += 1;
+            nav_path.index += 1;
         }
     }
 }
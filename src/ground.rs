@@ -1,11 +1,11 @@
 use std::sync::{Arc, RwLock};
 
-use bevy::{
-    prelude::*,
-    tasks::{AsyncComputeTaskPool, Task},
-};
+use bevy::prelude::*;
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::tasks::{AsyncComputeTaskPool, Task};
 use bevy_mod_picking::prelude::*;
 use bevy_rapier3d::prelude::{Collider, NoUserData, RapierConfiguration, RapierPhysicsPlugin};
+#[cfg(not(target_arch = "wasm32"))]
 use futures_lite::future;
 use oxidized_navigation::{
     debug_draw::{DrawNavMesh, DrawPath, OxidizedNavigationDebugDrawPlugin},
@@ -69,69 +69,223 @@ pub fn run_blocking_pathfinding(
 //  Async Pathfinding.
 //  Press A to run.
 //
-//  Running pathfinding in a task without blocking the frame.
+//  Running pathfinding in a task without blocking the frame on native targets; on
+//  wasm32 there's no thread pool to offload onto, so requests are instead computed
+//  synchronously one-per-frame (see `poll_pathfinding_tasks_system` below).
 //  Also check out Bevy's async compute example.
 //  https://github.com/bevyengine/bevy/blob/main/examples/async_tasks/async_compute.rs
 //
 
-// Holder resource for tasks.
+/// Fired once a queued pathfinding request finishes, on both native and wasm32.
+/// `requester` is whatever `queue_pathfinding` was called with, e.g. an enemy
+/// `Target` entity (see `enemy.rs`); debug key-bound requests pass `None`, and their
+/// path is drawn via `DrawPath` and not applied to any entity. `request_id` is
+/// echoed back unchanged so a caller that re-queues a request for the same
+/// `requester` (e.g. after the navmesh changed) can tell a stale result apart from
+/// the current one. `waypoints` is `None` when the request completed but no path
+/// was found, as distinct from the request still being in flight.
+#[derive(Event, Clone)]
+pub struct PathfindingComplete {
+    pub requester: Option<Entity>,
+    pub request_id: u64,
+    pub waypoints: Option<Vec<Vec3>>,
+}
+
+// Holder resource for pathfinding work. Native: real background tasks. Wasm32: a
+// plain queue, since `AsyncComputeTaskPool` has no worker threads to run on there.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default, Resource)]
+pub struct AsyncPathfindingTasks {
+    tasks: Vec<(Option<Entity>, u64, Task<Option<Vec<Vec3>>>)>,
+}
+
+#[cfg(target_arch = "wasm32")]
+struct PendingPath {
+    requester: Option<Entity>,
+    request_id: u64,
+    nav_mesh_lock: Arc<RwLock<NavMeshTiles>>,
+    nav_mesh_settings: NavMeshSettings,
+    start_pos: Vec3,
+    end_pos: Vec3,
+    position_search_radius: Option<f32>,
+}
+
+#[cfg(target_arch = "wasm32")]
 #[derive(Default, Resource)]
 pub struct AsyncPathfindingTasks {
-    tasks: Vec<Task<Option<Vec<Vec3>>>>,
+    queue: std::collections::VecDeque<PendingPath>,
 }
 
-// Queue up pathfinding tasks.
+/// Queue a pathfinding request from `start_pos` to `end_pos`. `requester` and
+/// `request_id` are handed back unchanged on `PathfindingComplete` once the request
+/// completes (found or not), so callers can correlate the result (e.g. with the
+/// entity that asked for it) and reject a stale one.
+pub fn queue_pathfinding(
+    tasks: &mut AsyncPathfindingTasks,
+    nav_mesh: &NavMesh,
+    nav_mesh_settings: &NavMeshSettings,
+    start_pos: Vec3,
+    end_pos: Vec3,
+    position_search_radius: Option<f32>,
+    requester: Option<Entity>,
+    request_id: u64,
+) {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let thread_pool = AsyncComputeTaskPool::get();
+        let task = thread_pool.spawn(async_path_find(
+            nav_mesh.get(),
+            nav_mesh_settings.clone(),
+            start_pos,
+            end_pos,
+            position_search_radius,
+        ));
+        tasks.tasks.push((requester, request_id, task));
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        tasks.queue.push_back(PendingPath {
+            requester,
+            request_id,
+            nav_mesh_lock: nav_mesh.get(),
+            nav_mesh_settings: nav_mesh_settings.clone(),
+            start_pos,
+            end_pos,
+            position_search_radius,
+        });
+    }
+}
+
+// Queue up a pathfinding request for the debug A keybinding.
 pub fn run_async_pathfinding(
     keys: Res<Input<KeyCode>>,
     nav_mesh_settings: Res<NavMeshSettings>,
     nav_mesh: Res<NavMesh>,
-    mut pathfinding_task: ResMut<AsyncPathfindingTasks>,
+    mut pathfinding_tasks: ResMut<AsyncPathfindingTasks>,
 ) {
     if !keys.just_pressed(KeyCode::A) {
         return;
     }
 
-    let thread_pool = AsyncComputeTaskPool::get();
-
-    let nav_mesh_lock = nav_mesh.get();
     let start_pos = Vec3::new(5.0, 1.0, 5.0);
     let end_pos = Vec3::new(-15.0, 1.0, -15.0);
 
-    let task = thread_pool.spawn(async_path_find(
-        nav_mesh_lock,
-        nav_mesh_settings.clone(),
+    queue_pathfinding(
+        &mut pathfinding_tasks,
+        &nav_mesh,
+        &nav_mesh_settings,
         start_pos,
         end_pos,
         None,
-    ));
+        None,
+        0, // no requester to correlate a stale result against
+    );
+}
+
+// Poll existing tasks, native version: non-blocking check of each background task.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn poll_pathfinding_tasks_system(
+    mut commands: Commands,
+    mut pathfinding_tasks: ResMut<AsyncPathfindingTasks>,
+    mut completed: EventWriter<PathfindingComplete>,
+) {
+    pathfinding_tasks.tasks.retain_mut(|(requester, request_id, task)| {
+        // `poll_once` returns `None` while the task is still running, and
+        // `Some(None)` once it's finished but `find_path` came back empty/`Err`.
+        // Collapsing both into one `None` via `unwrap_or` would mean a legitimately
+        // failed request never fires `PathfindingComplete` and the requester is
+        // stuck waiting forever, so they're handled as distinct cases here.
+        let Some(found_path) = future::block_on(future::poll_once(task)) else {
+            return true;
+        };
 
-    pathfinding_task.tasks.push(task);
+        info!("Async path task finished with result: {:?}", found_path);
+        if let Some(path) = &found_path {
+            if requester.is_none() {
+                commands.spawn(DrawPath {
+                    timer: Some(Timer::from_seconds(4.0, TimerMode::Once)),
+                    pulled_path: path.clone(),
+                    color: Color::BLUE,
+                });
+            }
+        }
+        completed.send(PathfindingComplete {
+            requester: *requester,
+            request_id: *request_id,
+            waypoints: found_path,
+        });
+
+        false
+    });
 }
 
-// Poll existing tasks.
+// Poll existing tasks, wasm32 version: there's no worker thread, so instead we
+// time-slice by computing at most one queued path per frame. This keeps any single
+// frame from stalling on a potentially expensive pathfinding call.
+#[cfg(target_arch = "wasm32")]
 pub fn poll_pathfinding_tasks_system(
     mut commands: Commands,
-    mut pathfinding_task: ResMut<AsyncPathfindingTasks>,
+    mut pathfinding_tasks: ResMut<AsyncPathfindingTasks>,
+    mut completed: EventWriter<PathfindingComplete>,
 ) {
-    // Go through and remove completed tasks.
-    pathfinding_task.tasks.retain_mut(|task| {
-        if let Some(string_path) = future::block_on(future::poll_once(task)).unwrap_or(None) {
-            info!("Async path task finished with result: {:?}", string_path);
+    let Some(request) = pathfinding_tasks.queue.pop_front() else {
+        return;
+    };
+
+    // As on the native path, a lock/pathfinding/string-pulling failure here must
+    // still notify the requester (with `waypoints: None`) rather than silently
+    // dropping the request, or it's stuck waiting on a result that will never come.
+    let waypoints = (|| {
+        let nav_mesh = request.nav_mesh_lock.read().ok()?;
+
+        let path = match find_polygon_path(
+            &nav_mesh,
+            &request.nav_mesh_settings,
+            request.start_pos,
+            request.end_pos,
+            request.position_search_radius,
+            Some(&[1.0, 0.5]),
+        ) {
+            Ok(path) => path,
+            Err(error) => {
+                error!("Error with pathfinding: {:?}", error);
+                return None;
+            }
+        };
+
+        match perform_string_pulling_on_path(&nav_mesh, request.start_pos, request.end_pos, &path)
+        {
+            Ok(waypoints) => Some(waypoints),
+            Err(error) => {
+                error!("Error with string path: {:?}", error);
+                None
+            }
+        }
+    })();
+
+    info!("Async path task finished with result: {:?}", waypoints);
+    if let Some(path) = &waypoints {
+        if request.requester.is_none() {
             commands.spawn(DrawPath {
                 timer: Some(Timer::from_seconds(4.0, TimerMode::Once)),
-                pulled_path: string_path.clone(),
+                pulled_path: path.clone(),
                 color: Color::BLUE,
             });
-
-            false
-        } else {
-            true
         }
+    }
+    completed.send(PathfindingComplete {
+        requester: request.requester,
+        request_id: request.request_id,
+        waypoints,
     });
 }
 
-/// Async wrapper function for path finding.
-pub async fn async_path_find(
+/// Async wrapper function for path finding. Native-only: on wasm32 there's no thread
+/// pool to spawn this onto, so `poll_pathfinding_tasks_system` runs the pathfinding
+/// synchronously (time-sliced) instead.
+#[cfg(not(target_arch = "wasm32"))]
+async fn async_path_find(
     nav_mesh_lock: Arc<RwLock<NavMeshTiles>>,
     nav_mesh_settings: NavMeshSettings,
     start_pos: Vec3,
@@ -189,6 +343,13 @@ pub fn setup_world_system(
         Camera3dBundle {
             transform: Transform::from_xyz(30.0, 25.0, 25.0)
                 .looking_at(Vec3::new(0.0, 2.0, 0.0), Vec3::Y),
+            // A level's `BloomConfig` (scene_config.rs) only has a visible effect on
+            // an HDR render target, so this is on unconditionally rather than toggled
+            // per-level.
+            camera: Camera {
+                hdr: true,
+                ..default()
+            },
             ..default()
         },
         RapierPickCamera::default(), // <- Sets the camera to use for picking.
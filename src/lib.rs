@@ -9,7 +9,9 @@ mod ground;
 mod loading;
 mod menu;
 mod player;
+mod scene_config;
 mod tower;
+mod wave;
 
 use crate::actions::ActionsPlugin;
 use crate::audio::InternalAudioPlugin;
@@ -18,7 +20,9 @@ use crate::ground::*;
 use crate::loading::LoadingPlugin;
 use crate::menu::MenuPlugin;
 use crate::player::PlayerPlugin;
+use crate::scene_config::SceneConfigPlugin;
 use crate::tower::TowerPlugin;
+use crate::wave::WavePlugin;
 
 use assets::GameAssets;
 use bevy::app::App;
@@ -49,6 +53,8 @@ enum GameState {
     Menu,
     // During this State the actual game logic is executed
     Playing,
+    // All of the current level's waves are cleared; a scene transition is pending
+    LevelComplete,
 }
 
 pub struct GamePlugin;
@@ -60,7 +66,7 @@ impl Plugin for GamePlugin {
                 LoadingPlugin,
                 // MenuPlugin,
                 // ActionsPlugin,
-                // InternalAudioPlugin,
+                InternalAudioPlugin,
                 PlayerPlugin,
                 //
                 DefaultPickingPlugins.build(),
@@ -78,7 +84,13 @@ impl Plugin for GamePlugin {
                     merge_region_area: 500,
                     max_contour_simplification_error: 1.1,
                     max_edge_length: 80,
-                    max_tile_generation_tasks: Some(9),
+                    // wasm32 has no worker threads to generate tiles on in parallel,
+                    // so it degrades to a single tile generation task at a time.
+                    max_tile_generation_tasks: if cfg!(target_arch = "wasm32") {
+                        Some(1)
+                    } else {
+                        Some(9)
+                    },
                 }),
                 OxidizedNavigationDebugDrawPlugin,
                 // The rapier plugin needs to be added for the scales of colliders to be correct if the scale of the entity is not uniformly 1.
@@ -86,12 +98,16 @@ impl Plugin for GamePlugin {
                 RapierPhysicsPlugin::<NoUserData>::default(),
             ))
             .insert_resource(RapierConfiguration {
-                physics_pipeline_active: false,
+                // Bullets deal damage through Rapier collision events now, so the
+                // pipeline needs to run; nothing in the game relies on Rapier's own
+                // force integration since movement is driven manually via Transform.
+                physics_pipeline_active: true,
                 ..Default::default()
             })
             .insert_resource(AsyncPathfindingTasks::default())
             .add_systems(Startup, (setup_world_system, info_system))
             .add_event::<DoSomethingComplex>()
+            .add_event::<PathfindingComplete>()
             .add_systems(
                 Update,
                 (
@@ -106,7 +122,9 @@ impl Plugin for GamePlugin {
             .add_systems(PreStartup, asset_loading)
             .add_plugins(TowerPlugin)
             .add_plugins(EnemyPlugin)
-            .add_plugins(BulletPlugin);
+            .add_plugins(BulletPlugin)
+            .add_plugins(WavePlugin)
+            .add_plugins(SceneConfigPlugin);
 
         #[cfg(debug_assertions)]
         {
@@ -0,0 +1,199 @@
+use bevy::core_pipeline::bloom::BloomSettings;
+use bevy::core_pipeline::clear_color::ClearColorConfig;
+use bevy::core_pipeline::prepass::{DepthPrepass, NormalPrepass};
+use bevy::gltf::GltfExtras;
+use bevy::pbr::{PointLightShadowMap, ScreenSpaceAmbientOcclusionBundle};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// Deserialized off marker objects in a level's `.glb` (the Blender "scene
+/// components" workflow), so each level can set its own mood without touching Rust.
+/// Applied to the `AmbientLight` resource and used as the camera clear color; it
+/// never touches per-entity light components, so those still win where they're set.
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct AmbientConfig {
+    pub color: Color,
+    pub brightness: f32,
+}
+
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct BloomConfig {
+    pub intensity: f32,
+}
+
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct SsaoConfig;
+
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct ShadowConfig {
+    pub map_resolution: usize,
+    pub enabled: bool,
+}
+
+pub struct SceneConfigPlugin;
+
+impl Plugin for SceneConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<AmbientConfig>()
+            .register_type::<BloomConfig>()
+            .register_type::<SsaoConfig>()
+            .register_type::<ShadowConfig>()
+            .add_systems(
+                Update,
+                (
+                    deserialize_scene_extras,
+                    apply_ambient_config,
+                    apply_bloom_config,
+                    apply_ssao_config,
+                    apply_shadow_config,
+                ),
+            );
+    }
+}
+
+/// Shape of the "extras" JSON object an artist attaches to a level's `.glb` scene
+/// node (the Blender "scene components" workflow). Bevy's gltf loader carries any
+/// such extras through verbatim as a `GltfExtras` component; this is just the schema
+/// we expect out of it. Any section left out of the JSON is simply not applied.
+#[derive(Deserialize)]
+struct SceneExtras {
+    #[serde(default)]
+    ambient: Option<AmbientExtras>,
+    #[serde(default)]
+    bloom: Option<BloomExtras>,
+    #[serde(default)]
+    ssao: bool,
+    #[serde(default)]
+    shadow: Option<ShadowExtras>,
+}
+
+#[derive(Deserialize)]
+struct AmbientExtras {
+    color: [f32; 3],
+    brightness: f32,
+}
+
+#[derive(Deserialize)]
+struct BloomExtras {
+    intensity: f32,
+}
+
+#[derive(Deserialize)]
+struct ShadowExtras {
+    map_resolution: usize,
+    enabled: bool,
+}
+
+/// Reads each newly-spawned `GltfExtras` and, if its JSON parses as `SceneExtras`,
+/// inserts the corresponding marker components onto the same entity so
+/// `apply_ambient_config`/`apply_bloom_config`/`apply_ssao_config`/
+/// `apply_shadow_config` pick them up via `Added<T>`. Extras that don't parse, or
+/// that don't carry any scene-component section, are left alone -- this is the only
+/// thing in the gltf node's extras that's interpreted this way.
+fn deserialize_scene_extras(
+    mut commands: Commands,
+    extras: Query<(Entity, &GltfExtras), Added<GltfExtras>>,
+) {
+    for (entity, extras) in &extras {
+        let Ok(parsed) = serde_json::from_str::<SceneExtras>(&extras.value) else {
+            continue;
+        };
+
+        let mut entity_commands = commands.entity(entity);
+        if let Some(ambient) = parsed.ambient {
+            entity_commands.insert(AmbientConfig {
+                color: Color::rgb(ambient.color[0], ambient.color[1], ambient.color[2]),
+                brightness: ambient.brightness,
+            });
+        }
+        if let Some(bloom) = parsed.bloom {
+            entity_commands.insert(BloomConfig {
+                intensity: bloom.intensity,
+            });
+        }
+        if parsed.ssao {
+            entity_commands.insert(SsaoConfig);
+        }
+        if let Some(shadow) = parsed.shadow {
+            entity_commands.insert(ShadowConfig {
+                map_resolution: shadow.map_resolution,
+                enabled: shadow.enabled,
+            });
+        }
+    }
+}
+
+fn apply_ambient_config(
+    mut ambient_light: ResMut<AmbientLight>,
+    mut cameras: Query<&mut Camera, With<Camera3d>>,
+    configs: Query<&AmbientConfig, Added<AmbientConfig>>,
+) {
+    // A level only ever carries one ambient config; last-one-wins if it somehow has more.
+    let Some(config) = configs.iter().last() else {
+        return;
+    };
+
+    ambient_light.color = config.color;
+    ambient_light.brightness = config.brightness;
+
+    for mut camera in &mut cameras {
+        camera.clear_color = ClearColorConfig::Custom(config.color);
+    }
+}
+
+fn apply_bloom_config(
+    mut commands: Commands,
+    cameras: Query<Entity, With<Camera3d>>,
+    configs: Query<&BloomConfig, Added<BloomConfig>>,
+) {
+    let Some(config) = configs.iter().last() else {
+        return;
+    };
+
+    for camera in &cameras {
+        commands.entity(camera).insert(BloomSettings {
+            intensity: config.intensity,
+            ..default()
+        });
+    }
+}
+
+fn apply_ssao_config(
+    mut commands: Commands,
+    mut msaa: ResMut<Msaa>,
+    cameras: Query<Entity, With<Camera3d>>,
+    configs: Query<(), Added<SsaoConfig>>,
+) {
+    if configs.is_empty() {
+        return;
+    }
+
+    // SSAO needs prepasses and is incompatible with MSAA.
+    *msaa = Msaa::Off;
+    for camera in &cameras {
+        commands.entity(camera).insert((
+            ScreenSpaceAmbientOcclusionBundle::default(),
+            DepthPrepass,
+            NormalPrepass,
+        ));
+    }
+}
+
+fn apply_shadow_config(
+    mut shadow_map: ResMut<PointLightShadowMap>,
+    mut point_lights: Query<&mut PointLight>,
+    configs: Query<&ShadowConfig, Added<ShadowConfig>>,
+) {
+    let Some(config) = configs.iter().last() else {
+        return;
+    };
+
+    shadow_map.size = config.map_resolution;
+    for mut light in &mut point_lights {
+        light.shadows_enabled = config.enabled;
+    }
+}
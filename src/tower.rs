@@ -1,11 +1,13 @@
 use bevy::{prelude::*, utils::FloatOrd};
 use bevy_mod_picking::prelude::*;
 use bevy_rapier3d::prelude::*;
+use oxidized_navigation::NavMeshAffector;
 
 use crate::{
     assets::GameAssets,
     bullet::{Bullet, Lifetime},
     enemy::Target,
+    player::Player,
     GameState,
 };
 
@@ -13,20 +15,39 @@ pub struct TowerPlugin;
 
 #[derive(Component)]
 pub struct Tower {
+    pub turret_type: TowerTurretType,
+    pub level: u32,
     pub shooting_timer: Timer,
     pub bullet_offset: Vec3,
     pub range: f32,
+    pub bullet_speed: f32,
+    pub damage: i32,
 }
 
 #[derive(Component)]
 pub struct TowerBase {}
 
+/// Local-space offset from a turret's root to its muzzle. Used both to compute where
+/// a fired bullet starts in world space and as the bullet's spawn transform relative
+/// to the tower (it's spawned as the tower's child), so the two stay in agreement
+/// regardless of where the tower itself was built.
+const MUZZLE_OFFSET: Vec3 = Vec3::new(0.0, 2.0, 0.0);
+
 impl Plugin for TowerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_base)
+        app.insert_resource(SelectedTurretType(TowerTurretType::Missile))
             .add_event::<BuildTower>()
-            .add_systems(Update, spawn_turret.run_if(on_event::<BuildTower>()))
-            .add_systems(Update, tower_shooting.run_if(in_state(GameState::Playing)));
+            .add_event::<TowerFiredEvent>()
+            .add_systems(Startup, spawn_base)
+            .add_systems(
+                Update,
+                (
+                    select_turret_type,
+                    spawn_turret.run_if(on_event::<BuildTower>()),
+                    tower_shooting,
+                )
+                    .run_if(in_state(GameState::Playing)),
+            );
     }
 }
 
@@ -44,66 +65,224 @@ fn spawn_base(mut commands: Commands, asset_server: Res<AssetServer>) {
         },
         Collider::cuboid(5.0, 5.0, 5.0),
         PickableBundle::default(),
-        On::<Pointer<Click>>::run(spawn_turret),
+        On::<Pointer<Click>>::run(queue_build),
     ));
 }
 
 // BUILD ===========
 
-#[derive(Clone)]
-enum TowerTurretType {
-    Missile = 0,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TowerTurretType {
+    Missile,
+    RapidFire,
+    Sniper,
+    Splash,
+}
+
+pub struct TowerStats {
+    pub cost: u32,
+    pub fire_rate: f32,
+    pub range: f32,
+    pub bullet_speed: f32,
+    pub damage: i32,
+    pub scene_asset: &'static str,
+}
+
+impl TowerTurretType {
+    pub fn stats(self) -> TowerStats {
+        match self {
+            TowerTurretType::Missile => TowerStats {
+                cost: 50,
+                fire_rate: 0.5,
+                range: 100.0,
+                bullet_speed: 5.0,
+                damage: 1,
+                scene_asset: "models/turret_0.glb#Scene0",
+            },
+            TowerTurretType::RapidFire => TowerStats {
+                cost: 75,
+                fire_rate: 0.15,
+                range: 60.0,
+                bullet_speed: 7.0,
+                damage: 1,
+                scene_asset: "models/turret_0.glb#Scene0",
+            },
+            TowerTurretType::Sniper => TowerStats {
+                cost: 100,
+                fire_rate: 1.5,
+                range: 200.0,
+                bullet_speed: 15.0,
+                damage: 5,
+                scene_asset: "models/turret_0.glb#Scene0",
+            },
+            TowerTurretType::Splash => TowerStats {
+                cost: 125,
+                fire_rate: 1.0,
+                range: 80.0,
+                bullet_speed: 4.0,
+                damage: 3,
+                scene_asset: "models/turret_0.glb#Scene0",
+            },
+        }
+    }
+
+    /// Stats at upgrade `level`: each upgrade level scales range/damage up and fire-rate down.
+    fn upgraded(self, level: u32) -> TowerStats {
+        let mut stats = self.stats();
+        let scale = 1.0 + 0.25 * level as f32;
+        stats.cost = (stats.cost as f32 * scale).round() as u32;
+        stats.range *= scale;
+        stats.damage = (stats.damage as f32 * scale).round() as i32;
+        stats.fire_rate /= scale;
+        stats
+    }
+}
+
+/// Which `TowerTurretType` the next `BuildTower` click will place. Stands in for a
+/// proper build menu UI: press 1-4 to pick a turret before clicking the base.
+#[derive(Resource)]
+struct SelectedTurretType(TowerTurretType);
+
+fn select_turret_type(keys: Res<Input<KeyCode>>, mut selected: ResMut<SelectedTurretType>) {
+    let turret_type = if keys.just_pressed(KeyCode::Key1) {
+        Some(TowerTurretType::Missile)
+    } else if keys.just_pressed(KeyCode::Key2) {
+        Some(TowerTurretType::RapidFire)
+    } else if keys.just_pressed(KeyCode::Key3) {
+        Some(TowerTurretType::Sniper)
+    } else if keys.just_pressed(KeyCode::Key4) {
+        Some(TowerTurretType::Splash)
+    } else {
+        None
+    };
+
+    if let Some(turret_type) = turret_type {
+        info!("Selected turret type: {:?}", turret_type);
+        selected.0 = turret_type;
+    }
 }
 
 #[derive(Clone, Event)]
-pub struct BuildTower(TowerTurretType, Transform);
+pub struct BuildTower(TowerTurretType, Vec3);
 
-impl From<ListenerInput<Pointer<Click>>> for BuildTower {
-    fn from(event: ListenerInput<Pointer<Click>>) -> Self {
-        print!("event.target:{:?}", event.target);
-        let translation = Vec3::new(0.0, 2.0, -2.0);
-        let transform = Transform::from_translation(translation);
+/// Click callback on `TowerBase`: reads the currently selected turret type and the
+/// clicked world position off the pick event and turns them into a `BuildTower`.
+fn queue_build(
+    event: Listener<Pointer<Click>>,
+    selected: Res<SelectedTurretType>,
+    mut build_events: EventWriter<BuildTower>,
+) {
+    let position = event.hit.position.unwrap_or(Vec3::new(0.0, 2.0, -2.0));
+    build_events.send(BuildTower(selected.0, position));
+}
+
+fn spawn_turret(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut build_events: EventReader<BuildTower>,
+    mut player: Query<&mut Player>,
+) {
+    let mut player = player.single_mut();
 
-        BuildTower(TowerTurretType::Missile, transform)
+    for BuildTower(turret_type, position) in build_events.iter() {
+        let stats = turret_type.stats();
+        if player.money < stats.cost {
+            info!(
+                "Not enough money to build a {:?} turret (needs {}, have {})",
+                turret_type, stats.cost, player.money
+            );
+            continue;
+        }
+        player.money -= stats.cost;
+
+        let transform = Transform::from_translation(*position);
+        commands.spawn((
+            Tower {
+                turret_type: *turret_type,
+                level: 0,
+                shooting_timer: Timer::from_seconds(stats.fire_rate, TimerMode::Repeating),
+                bullet_offset: MUZZLE_OFFSET,
+                range: stats.range,
+                bullet_speed: stats.bullet_speed,
+                damage: stats.damage,
+            },
+            Name::new("tower_turret"),
+            SceneBundle {
+                scene: asset_server.load(stats.scene_asset),
+                transform,
+                ..default()
+            },
+            Collider::cuboid(5.0, 5.0, 5.0),
+            // So `replan_targets_on_affector_change` (enemy.rs) re-routes targets
+            // around built towers instead of letting them walk straight through.
+            NavMeshAffector,
+            PickableBundle::default(),
+            On::<Pointer<Click>>::run(upgrade_turret),
+        ));
     }
 }
 
-pub fn spawn_turret(
+/// Click callback on an existing `Tower`: spends money to bump its upgrade level and
+/// respawn its scene from the turret type's `scene_asset`. That asset is the same at
+/// every upgrade level, so an upgraded tower looks the same as before.
+fn upgrade_turret(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     event: Listener<Pointer<Click>>,
+    mut towers: Query<&mut Tower>,
+    mut player: Query<&mut Player>,
 ) {
-    info!("🦀 spawn_turret: {:#?}", event.target);
-    let translation = Vec3::new(0.0, 2.0, -2.0);
-    let transform = Transform::from_translation(translation);
+    let Ok(mut tower) = towers.get_mut(event.target) else {
+        return;
+    };
 
-    commands.spawn((
-        Tower {
-            shooting_timer: Timer::from_seconds(0.5, TimerMode::Repeating),
-            bullet_offset: translation,
-            range: 100.0,
-        },
-        Name::new("tower_turret"),
-        SceneBundle {
-            scene: asset_server.load("models/turret_0.glb#Scene0"),
-            transform,
-            ..default()
-        },
-        Collider::cuboid(5.0, 5.0, 5.0),
-        PickableBundle::default(),
-        // On::<Pointer<Click>>::send_event::<BuildTower>(),
-        // On::<Pointer<Click>>::send_event::<Shutdown>(),
-    ));
+    let stats = tower.turret_type.upgraded(tower.level + 1);
+    let mut player = player.single_mut();
+    if player.money < stats.cost {
+        info!(
+            "Not enough money to upgrade {:?} turret (needs {}, have {})",
+            tower.turret_type, stats.cost, player.money
+        );
+        return;
+    }
+    player.money -= stats.cost;
+
+    tower.level += 1;
+    tower.shooting_timer = Timer::from_seconds(stats.fire_rate, TimerMode::Repeating);
+    tower.range = stats.range;
+    tower.bullet_speed = stats.bullet_speed;
+    tower.damage = stats.damage;
+
+    info!(
+        "Upgraded {:?} turret to level {}",
+        tower.turret_type, tower.level
+    );
+    // The tower entity already carries a Handle<Scene> from its original
+    // SceneBundle, with the scene's nodes spawned in as its children. Just
+    // overwriting the handle isn't guaranteed to clean up that existing instance,
+    // so despawn it explicitly before inserting the new one.
+    commands
+        .entity(event.target)
+        .despawn_descendants()
+        .insert(asset_server.load::<Scene>(stats.scene_asset));
 }
 
 // HUNT ===========
 
+/// Carries a fired bullet's spawn position for whatever wants to react to a tower
+/// firing (currently just positional audio).
+#[derive(Clone, Event)]
+pub struct TowerFiredEvent {
+    pub position: Vec3,
+}
+
 fn tower_shooting(
     mut commands: Commands,
     mut towers: Query<(Entity, &mut Tower, &GlobalTransform)>,
     targets: Query<&GlobalTransform, With<Target>>,
     game_assets: Res<GameAssets>,
     time: Res<Time>,
+    mut fired_events: EventWriter<TowerFiredEvent>,
 ) {
     for (tower_ent, mut tower, transform) in &mut towers {
         tower.shooting_timer.tick(time.delta());
@@ -123,12 +302,17 @@ fn tower_shooting(
             if let Some(direction) = direction {
                 let bullet = Bullet {
                     direction,
-                    speed: 5.0,
+                    speed: tower.bullet_speed,
+                    damage: tower.damage,
                 };
 
                 let translation = tower.bullet_offset;
                 let transform = Transform::from_translation(translation);
 
+                fired_events.send(TowerFiredEvent {
+                    position: bullet_spawn,
+                });
+
                 commands.entity(tower_ent).with_children(|commands| {
                     println!("tower_shooting");
                     commands
@@ -141,6 +325,17 @@ fn tower_shooting(
                             timer: Timer::from_seconds(10.0, TimerMode::Once),
                         })
                         .insert(bullet)
+                        // Driven manually by `move_bullets`; kinematic so Rapier still
+                        // tracks the collider for intersection events as it moves.
+                        .insert(RigidBody::KinematicPositionBased)
+                        .insert(Collider::ball(0.2))
+                        .insert(Sensor)
+                        .insert(ActiveEvents::COLLISION_EVENTS)
+                        // Kinematic/Fixed isn't in Rapier's default ActiveCollisionTypes
+                        // (only *_DYNAMIC and DYNAMIC_STATIC are opt-in by default), so
+                        // without this the narrow phase never evaluates this pair and
+                        // no CollisionEvent is ever generated against a Target.
+                        .insert(ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_STATIC)
                         .insert(Name::new("Bullet"));
                 });
             }
@@ -0,0 +1,237 @@
+use bevy::core_pipeline::bloom::BloomSettings;
+use bevy::core_pipeline::clear_color::ClearColorConfig;
+use bevy::pbr::{PointLightShadowMap, ScreenSpaceAmbientOcclusionBundle};
+use bevy::prelude::*;
+use oxidized_navigation::NavMeshAffector;
+
+use crate::{
+    assets::GameAssets,
+    enemy::{spawn_target, Health, Target, TargetDeathEvent},
+    tower::Tower,
+    GameState,
+};
+
+#[derive(Clone)]
+pub struct WaveConfig {
+    pub enemy_count: u32,
+    pub spawn_interval: f32,
+    pub enemy_speed: f32,
+    pub enemy_health: i32,
+}
+
+/// Drives a level's tower-defense pacing: how many waves, what each wave spawns, and
+/// what scene to load once the level is cleared.
+#[derive(Resource, Clone)]
+pub struct LevelConfig {
+    pub waves: Vec<WaveConfig>,
+    pub next_scene: Option<Handle<Scene>>,
+}
+
+impl Default for LevelConfig {
+    fn default() -> Self {
+        //Could be loaded from a config or level file
+        LevelConfig {
+            waves: vec![
+                WaveConfig {
+                    enemy_count: 3,
+                    spawn_interval: 1.5,
+                    enemy_speed: 0.25,
+                    enemy_health: 3,
+                },
+                WaveConfig {
+                    enemy_count: 5,
+                    spawn_interval: 1.0,
+                    enemy_speed: 0.3,
+                    enemy_health: 4,
+                },
+                WaveConfig {
+                    enemy_count: 8,
+                    spawn_interval: 0.75,
+                    enemy_speed: 0.35,
+                    enemy_health: 5,
+                },
+            ],
+            next_scene: None,
+        }
+    }
+}
+
+#[derive(Resource)]
+struct WaveState {
+    current_wave: usize,
+    spawned_this_wave: u32,
+    spawn_timer: Timer,
+}
+
+impl Default for WaveState {
+    fn default() -> Self {
+        WaveState {
+            current_wave: 0,
+            spawned_this_wave: 0,
+            spawn_timer: Timer::from_seconds(0.0, TimerMode::Once),
+        }
+    }
+}
+
+/// Marks a sensor zone placed in a level's `.glb` scene, reserved for a future
+/// player-controlled entity to walk into once a level is cleared. Nothing in this
+/// template currently spawns a moving, collidable player entity, so a zone can never
+/// actually be entered -- `advance_level` doesn't wait on it and transitions as soon
+/// as the level's last wave is cleared, regardless of whether a zone is present.
+#[derive(Component)]
+pub struct LevelTransition;
+
+/// Fires when a wave -- including a level's last one -- is cleared, letting other
+/// systems react without polling `LevelConfig`/`WaveState` every frame.
+#[derive(Clone, Event)]
+pub struct WaveClearedEvent;
+
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LevelConfig::default())
+            .insert_resource(WaveState::default())
+            .add_event::<WaveClearedEvent>()
+            .add_systems(OnEnter(GameState::Playing), start_first_wave)
+            .add_systems(
+                Update,
+                (spawn_wave_enemies, check_wave_complete).run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnEnter(GameState::LevelComplete), advance_level);
+    }
+}
+
+fn start_first_wave(mut wave_state: ResMut<WaveState>, level: Res<LevelConfig>) {
+    wave_state.current_wave = 0;
+    wave_state.spawned_this_wave = 0;
+    if let Some(wave) = level.waves.first() {
+        wave_state.spawn_timer = Timer::from_seconds(wave.spawn_interval, TimerMode::Repeating);
+    }
+}
+
+fn spawn_wave_enemies(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    level: Res<LevelConfig>,
+    mut wave_state: ResMut<WaveState>,
+    time: Res<Time>,
+) {
+    let Some(wave) = level.waves.get(wave_state.current_wave) else {
+        return;
+    };
+    if wave_state.spawned_this_wave >= wave.enemy_count {
+        return;
+    }
+
+    wave_state.spawn_timer.tick(time.delta());
+    if !wave_state.spawn_timer.just_finished() {
+        return;
+    }
+
+    // Fan enemies out along the spawn edge so they don't stack on top of each other.
+    let offset = wave_state.spawned_this_wave as f32;
+    let translation = Vec3::new(-2.0 - offset, 0.0, 2.5);
+    spawn_target(
+        &mut commands,
+        &game_assets,
+        translation,
+        wave.enemy_speed,
+        wave.enemy_health,
+    );
+    wave_state.spawned_this_wave += 1;
+}
+
+/// Advances to the next wave, or to `GameState::LevelComplete`, once every `Target`
+/// from the current wave has died.
+fn check_wave_complete(
+    mut death_events: EventReader<TargetDeathEvent>,
+    targets: Query<&Health, With<Target>>,
+    level: Res<LevelConfig>,
+    mut wave_state: ResMut<WaveState>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut wave_cleared_events: EventWriter<WaveClearedEvent>,
+) {
+    // `.iter()` (not `.is_empty()`) so the reader's cursor actually advances; otherwise
+    // events keep being "seen" for their whole default lifetime regardless of whether
+    // this system already acted on them this frame. Targets at zero health count as
+    // dead: `target_death` may have queued their despawn this frame without it applying yet.
+    if death_events.iter().count() == 0 || targets.iter().any(|health| health.value > 0) {
+        return;
+    }
+
+    let Some(wave) = level.waves.get(wave_state.current_wave) else {
+        return;
+    };
+    if wave_state.spawned_this_wave < wave.enemy_count {
+        return;
+    }
+
+    wave_cleared_events.send(WaveClearedEvent);
+    wave_state.current_wave += 1;
+    match level.waves.get(wave_state.current_wave) {
+        Some(next_wave) => {
+            wave_state.spawned_this_wave = 0;
+            wave_state.spawn_timer = Timer::from_seconds(next_wave.spawn_interval, TimerMode::Repeating);
+        }
+        None => next_state.set(GameState::LevelComplete),
+    }
+}
+
+/// Despawns the current level's enemies/towers/affectors and loads `next_scene`, then
+/// drops back into `GameState::Playing` so the new level's waves can start. Runs once
+/// on entering `GameState::LevelComplete` -- it used to wait for a `CollisionEvent`
+/// against a `LevelTransition` zone, but nothing in this template ever spawns a
+/// moving, collidable player entity that could generate one, so a level with a zone
+/// would stall in `LevelComplete` forever. See `LevelTransition`'s doc comment.
+fn advance_level(
+    mut commands: Commands,
+    level: Res<LevelConfig>,
+    mut wave_state: ResMut<WaveState>,
+    mut next_state: ResMut<NextState<GameState>>,
+    targets: Query<Entity, With<Target>>,
+    towers: Query<Entity, With<Tower>>,
+    affectors: Query<Entity, With<NavMeshAffector>>,
+    mut ambient_light: ResMut<AmbientLight>,
+    mut shadow_map: ResMut<PointLightShadowMap>,
+    mut msaa: ResMut<Msaa>,
+    mut cameras: Query<(Entity, &mut Camera), With<Camera3d>>,
+) {
+    for entity in &targets {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in &towers {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in &affectors {
+        // The old level's geometry no longer applies; the navmesh rebuilds from
+        // whatever `NavMeshAffector`s the next scene spawns.
+        commands.entity(entity).despawn_recursive();
+    }
+
+    // scene_config.rs only reacts to a marker component being `Added<T>`, so a level
+    // that doesn't define e.g. a `BloomConfig` would otherwise silently keep whatever
+    // the outgoing level applied. Reset to defaults here so each level starts from a
+    // neutral look and only diverges from it if its own scene defines a marker. Point
+    // lights keep whatever `shadows_enabled` a `ShadowConfig` last set on them.
+    *ambient_light = AmbientLight::default();
+    *shadow_map = PointLightShadowMap::default();
+    *msaa = Msaa::default();
+    for (camera_entity, mut camera) in &mut cameras {
+        camera.clear_color = ClearColorConfig::default();
+        commands
+            .entity(camera_entity)
+            .remove::<BloomSettings>()
+            .remove::<ScreenSpaceAmbientOcclusionBundle>();
+    }
+
+    if let Some(next_scene) = &level.next_scene {
+        commands.spawn(SceneBundle {
+            scene: next_scene.clone(),
+            ..default()
+        });
+    }
+
+    *wave_state = WaveState::default();
+    next_state.set(GameState::Playing);
+}